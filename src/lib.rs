@@ -1,6 +1,19 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[cfg(not(feature = "is_sync"))]
+use std::pin::Pin;
+#[cfg(not(feature = "is_sync"))]
+use std::task::{Context, Poll};
+
+#[cfg(not(feature = "is_sync"))]
+use futures::Stream;
+
 use maybe_async::maybe_async;
 use serde::{de::DeserializeOwned, Deserialize};
-use serde_json::{json, Map, Value};
+use serde_json::{Map, Value};
 use thiserror::Error;
 
 #[cfg(not(feature = "is_sync"))]
@@ -11,6 +24,22 @@ use reqwest::blocking::Client as HttpClient;
 
 const DEFAULT_VIZIER_TAP_URL: &str = "http://tapvizier.u-strasbg.fr/TAPVizieR/tap/sync";
 
+/// How often an async job's phase is polled while waiting for completion.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long [`QueryJob::wait`] waits before giving up with [`VizierError::JobTimeout`].
+const DEFAULT_MAX_WAIT: Duration = Duration::from_secs(300);
+
+/// Number of attempts (initial + retries) made for a transient failure.
+const DEFAULT_MAX_ATTEMPTS: u32 = 4;
+/// Base delay for exponential backoff between retries.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Upper bound on the exponential part of a backoff delay; jitter is added on top.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Consecutive failures after which a host's breaker trips open.
+const BREAKER_FAILURE_THRESHOLD: u32 = 5;
+/// Cooldown before an open breaker admits a trial request.
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
 #[cfg(not(feature = "is_sync"))]
 macro_rules! maybe_await {
     ($future:expr) => {
@@ -25,6 +54,18 @@ macro_rules! maybe_await {
     };
 }
 
+/// Suspend the current task for `duration`, using the runtime's sleep under
+/// async and a plain thread sleep when built with `is_sync`.
+#[cfg(not(feature = "is_sync"))]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(feature = "is_sync")]
+fn sleep(duration: Duration) {
+    std::thread::sleep(duration);
+}
+
 #[derive(Error, Debug)]
 pub enum VizierError {
     #[error("Request failed: {0}")]
@@ -35,17 +76,177 @@ pub enum VizierError {
     UnexpectedSchema(String),
     #[error("Failed to deserialize response: {0}")]
     DeserializationFailed(serde_json::Error),
+    #[error("Query job failed in phase {0}")]
+    JobFailed(Phase),
+    #[error("Query job did not complete within {0:?}")]
+    JobTimeout(Duration),
+    #[error("Circuit breaker open for host {0}")]
+    CircuitOpen(String),
+    #[error("Rate limited; retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
     #[error("{0}")]
     Other(String),
 }
 
+/// Rate-limit state parsed from a response's `Retry-After` and `X-RateLimit-*`
+/// headers.
+///
+/// Fields are optional because a given mirror may advertise only some of them;
+/// the most recent snapshot is available through [`Client::last_limits`].
+#[derive(Debug, Clone, Default)]
+pub struct RateLimit {
+    /// Ceiling on requests per window, from `X-RateLimit-Limit`.
+    pub limit: Option<u64>,
+    /// Requests left in the current window, from `X-RateLimit-Remaining`.
+    pub remaining: Option<u64>,
+    /// Window reset, from `X-RateLimit-Reset` (seconds, as sent by the server).
+    pub reset: Option<u64>,
+    /// Suggested wait before retrying, from `Retry-After`.
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let number = |name: &str| -> Option<u64> {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| s.trim().parse::<u64>().ok())
+        };
+
+        RateLimit {
+            limit: number("x-ratelimit-limit"),
+            remaining: number("x-ratelimit-remaining"),
+            reset: number("x-ratelimit-reset"),
+            retry_after: number("retry-after").map(Duration::from_secs),
+        }
+    }
+
+    /// Whether the window is exhausted (`remaining` has reached zero).
+    fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+/// Per-host failure tracking backing the circuit breaker.
+#[derive(Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    last_failure: Option<Instant>,
+}
+
+impl Breaker {
+    /// Whether the breaker is currently open and still within its cooldown, in
+    /// which case new requests should be short-circuited.
+    fn is_open(&self) -> bool {
+        if self.consecutive_failures < BREAKER_FAILURE_THRESHOLD {
+            return false;
+        }
+        match self.last_failure {
+            Some(at) => at.elapsed() < BREAKER_COOLDOWN,
+            None => false,
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.last_failure = None;
+    }
+
+    fn record_failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_failure = Some(Instant::now());
+    }
+}
+
+/// Execution phase of an asynchronous (UWS) query job.
+///
+/// The IVOA Universal Worker Service exposes a small state machine for each
+/// job; only the variants VizieR actually emits are modelled explicitly, with
+/// anything unrecognised preserved in [`Phase::Unknown`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Pending,
+    Queued,
+    Executing,
+    Completed,
+    Error,
+    Aborted,
+    Unknown(String),
+}
+
+impl Phase {
+    fn parse(raw: &str) -> Self {
+        match raw.trim() {
+            "PENDING" => Phase::Pending,
+            "QUEUED" => Phase::Queued,
+            "EXECUTING" => Phase::Executing,
+            "COMPLETED" => Phase::Completed,
+            "ERROR" => Phase::Error,
+            "ABORTED" => Phase::Aborted,
+            other => Phase::Unknown(other.to_string()),
+        }
+    }
+
+    /// Whether the job has reached a state in which it will no longer change.
+    fn is_terminal(&self) -> bool {
+        matches!(self, Phase::Completed | Phase::Error | Phase::Aborted)
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Pending => write!(f, "PENDING"),
+            Phase::Queued => write!(f, "QUEUED"),
+            Phase::Executing => write!(f, "EXECUTING"),
+            Phase::Completed => write!(f, "COMPLETED"),
+            Phase::Error => write!(f, "ERROR"),
+            Phase::Aborted => write!(f, "ABORTED"),
+            Phase::Unknown(raw) => write!(f, "{raw}"),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct ColumnMetadata {
     pub name: String,
+    #[serde(default)]
     pub description: String,
     pub arraysize: Option<String>,
     pub unit: Option<String>,
+    #[serde(default)]
     pub ucd: String,
+    /// VOTable column datatype (e.g. `double`, `int`, `char`). Only populated
+    /// by the VOTable result path; `None` for the JSON format, which does not
+    /// carry it.
+    #[serde(default)]
+    pub datatype: Option<String>,
+}
+
+/// Result serialisation requested from the TAP endpoint.
+///
+/// VizieR's JSON is the historical default, but VOTable is TAP's canonical
+/// format and carries the richest column metadata (datatypes, units, null
+/// handling); CSV/TSV are offered for lightweight tabular consumption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    VoTable,
+    Csv,
+    Tsv,
+}
+
+impl OutputFormat {
+    /// Value passed in the TAP `FORMAT` request parameter.
+    fn as_tap(&self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::VoTable => "votable",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Tsv => "tsv",
+        }
+    }
 }
 
 #[derive(Deserialize)]
@@ -55,6 +256,7 @@ struct ResponseSchema {
     data: Vec<Vec<Value>>,
 }
 
+#[derive(Debug)]
 pub struct QueryResult<T> {
     meta: Vec<ColumnMetadata>,
     data: Vec<T>,
@@ -78,73 +280,915 @@ impl<T> QueryResult<T> {
     }
 }
 
-pub struct Client {
+/// HTTP verb used by the [`HttpBackend`] abstraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// Backend-agnostic description of a single HTTP request.
+///
+/// The query parameters and (form-encoded) body are kept as plain key/value
+/// pairs so any backend can reconstruct the request without depending on
+/// reqwest's builder types.
+#[derive(Debug, Clone)]
+pub struct BackendRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub form: Option<Vec<(String, String)>>,
+}
+
+/// Backend-agnostic HTTP response: status code, the (possibly redirected) final
+/// URL, response headers, and the raw body bytes.
+#[derive(Debug, Clone)]
+pub struct BackendResponse {
+    pub status: u16,
+    pub url: String,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl BackendResponse {
+    /// Whether the status code is in the 2xx range.
+    fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// A [`VizierError::NonSuccessStatus`] carrying this response's status.
+    fn status_error(&self) -> VizierError {
+        let status = reqwest::StatusCode::from_u16(self.status)
+            .unwrap_or(reqwest::StatusCode::INTERNAL_SERVER_ERROR);
+        VizierError::NonSuccessStatus(status)
+    }
+
+    /// Response body decoded as UTF-8, lossily.
+    fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+}
+
+/// Extract the host portion of a URL string without pulling in a full parser.
+fn host_of(url: &str) -> String {
+    let without_scheme = url.split("://").nth(1).unwrap_or(url);
+    without_scheme
+        .split(['/', ':', '?'])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Pluggable HTTP transport.
+///
+/// Implement this to drive [`Client`] with a client other than reqwest — for
+/// instance surf, ureq, or a mock that replays canned responses in offline
+/// tests of [`Client::query`].
+#[maybe_async]
+pub trait HttpBackend: Send + Sync {
+    async fn execute(&self, request: BackendRequest) -> Result<BackendResponse, VizierError>;
+}
+
+/// Default [`HttpBackend`] backed by reqwest (`reqwest::blocking` under `is_sync`).
+pub struct ReqwestBackend {
+    client: HttpClient,
+}
+
+impl ReqwestBackend {
+    pub fn new() -> Self {
+        Self {
+            client: HttpClient::new(),
+        }
+    }
+}
+
+impl Default for ReqwestBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[maybe_async]
+impl HttpBackend for ReqwestBackend {
+    async fn execute(&self, request: BackendRequest) -> Result<BackendResponse, VizierError> {
+        let mut builder = match request.method {
+            HttpMethod::Get => self.client.get(&request.url),
+            HttpMethod::Post => self.client.post(&request.url),
+        };
+        if !request.query.is_empty() {
+            builder = builder.query(&request.query);
+        }
+        if let Some(form) = &request.form {
+            builder = builder.form(form);
+        }
+
+        let response = maybe_await!(builder.send()).map_err(VizierError::RequestFailed)?;
+        let status = response.status().as_u16();
+        let url = response.url().as_str().to_string();
+        let headers = response.headers().clone();
+        let body = maybe_await!(response.bytes())
+            .map_err(VizierError::RequestFailed)?
+            .to_vec();
+
+        Ok(BackendResponse {
+            status,
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+pub struct Client<B = ReqwestBackend> {
     tap_url: String,
-    http_client: HttpClient,
+    backend: B,
+    poll_interval: Duration,
+    max_wait: Duration,
+    max_attempts: u32,
+    throttle: bool,
+    breakers: Mutex<HashMap<String, Breaker>>,
+    last_limits: Mutex<Option<RateLimit>>,
 }
 
-impl Client {
+impl Client<ReqwestBackend> {
     pub fn new(tap_url: &str) -> Self {
+        Self::with_backend(tap_url, ReqwestBackend::new())
+    }
+}
+
+impl<B: HttpBackend> Client<B> {
+    /// Construct a client driven by a custom [`HttpBackend`].
+    pub fn with_backend(tap_url: &str, backend: B) -> Self {
         Self {
             tap_url: tap_url.to_string(),
-            http_client: HttpClient::new(),
+            backend,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            max_wait: DEFAULT_MAX_WAIT,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            throttle: false,
+            breakers: Mutex::new(HashMap::new()),
+            last_limits: Mutex::new(None),
+        }
+    }
+
+    /// Maximum number of attempts (initial request plus retries) made before a
+    /// transient failure is surfaced to the caller.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Enable client-side throttling: when the server reports no remaining
+    /// quota, wait out the advertised `Retry-After` before the next request
+    /// instead of hammering the endpoint.
+    pub fn with_throttle(mut self, throttle: bool) -> Self {
+        self.throttle = throttle;
+        self
+    }
+
+    /// Rate-limit snapshot parsed from the most recent response, if any.
+    pub fn last_limits(&self) -> Option<RateLimit> {
+        self.last_limits.lock().unwrap().clone()
+    }
+
+    /// Interval between phase polls for asynchronous jobs.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Maximum time [`Client::query_async`] waits for a job to complete before
+    /// returning [`VizierError::JobTimeout`].
+    pub fn with_max_wait(mut self, max_wait: Duration) -> Self {
+        self.max_wait = max_wait;
+        self
+    }
+
+    /// Endpoint used to submit asynchronous jobs, derived from [`Self::tap_url`]
+    /// by swapping the trailing `/sync` segment for `/async`.
+    fn async_url(&self) -> String {
+        match self.tap_url.strip_suffix("/sync") {
+            Some(base) => format!("{base}/async"),
+            None => self.tap_url.clone(),
+        }
+    }
+
+    /// Execute `request` through the configured [`HttpBackend`], retrying
+    /// transient failures with exponential backoff and guarding the target host
+    /// with a circuit breaker.
+    ///
+    /// Connection errors, HTTP 429 and 5xx responses are treated as transient
+    /// and retried up to [`Self::max_attempts`]; repeated failures trip the
+    /// host's breaker, after which requests short-circuit with
+    /// [`VizierError::CircuitOpen`] until the cooldown elapses.
+    #[maybe_async]
+    async fn send_resilient(
+        &self,
+        request: BackendRequest,
+    ) -> Result<BackendResponse, VizierError> {
+        let host = host_of(&request.url);
+
+        if self.breaker_is_open(&host) {
+            return Err(VizierError::CircuitOpen(host));
+        }
+
+        maybe_await!(self.await_throttle());
+
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let outcome = maybe_await!(self.backend.execute(request.clone()));
+
+            let (transient, rate_limited, retry_after) = match &outcome {
+                Ok(response) => {
+                    let limits = RateLimit::from_headers(&response.headers);
+                    let retry_after = limits.retry_after;
+                    *self.last_limits.lock().unwrap() = Some(limits);
+
+                    let rate_limited = response.status == 429;
+                    let server_error = (500..600).contains(&response.status);
+                    (rate_limited || server_error, rate_limited, retry_after)
+                }
+                Err(_) => (true, false, None),
+            };
+
+            if !transient {
+                self.record_breaker_success(&host);
+                return outcome;
+            }
+
+            if attempt >= self.max_attempts {
+                self.record_breaker_failure(&host);
+                if rate_limited {
+                    return Err(VizierError::RateLimited {
+                        retry_after: retry_after.unwrap_or(BREAKER_COOLDOWN),
+                    });
+                }
+                return outcome;
+            }
+
+            // Honour a server-provided Retry-After, else fall back to backoff.
+            maybe_await!(sleep(retry_after.unwrap_or_else(|| backoff_delay(attempt))));
+        }
+    }
+
+    /// Execute `request` exactly once through the backend, without the retry
+    /// layer, for non-idempotent calls. The breaker guard, throttle wait and
+    /// rate-limit bookkeeping still apply, and a transient failure is surfaced
+    /// (not replayed) so the caller can decide whether to resubmit.
+    #[maybe_async]
+    async fn send_once(&self, request: BackendRequest) -> Result<BackendResponse, VizierError> {
+        let host = host_of(&request.url);
+
+        if self.breaker_is_open(&host) {
+            return Err(VizierError::CircuitOpen(host));
+        }
+
+        maybe_await!(self.await_throttle());
+
+        let outcome = maybe_await!(self.backend.execute(request));
+        let (transient, rate_limited, retry_after) = match &outcome {
+            Ok(response) => {
+                let limits = RateLimit::from_headers(&response.headers);
+                let retry_after = limits.retry_after;
+                *self.last_limits.lock().unwrap() = Some(limits);
+
+                let rate_limited = response.status == 429;
+                let server_error = (500..600).contains(&response.status);
+                (rate_limited || server_error, rate_limited, retry_after)
+            }
+            Err(_) => (true, false, None),
+        };
+
+        if !transient {
+            self.record_breaker_success(&host);
+            return outcome;
+        }
+
+        self.record_breaker_failure(&host);
+        if rate_limited {
+            return Err(VizierError::RateLimited {
+                retry_after: retry_after.unwrap_or(BREAKER_COOLDOWN),
+            });
+        }
+        outcome
+    }
+
+    /// Wait out a previously-advertised exhausted rate-limit window before
+    /// sending, when throttling is enabled.
+    #[maybe_async]
+    async fn await_throttle(&self) {
+        if !self.throttle {
+            return;
+        }
+        // Copy the delay out and drop the guard before awaiting.
+        let throttle_for = {
+            let limits = self.last_limits.lock().unwrap();
+            limits
+                .as_ref()
+                .filter(|l| l.is_exhausted())
+                .and_then(|l| l.retry_after)
+        };
+        if let Some(retry_after) = throttle_for {
+            maybe_await!(sleep(retry_after));
         }
     }
 
+    fn breaker_is_open(&self, host: &str) -> bool {
+        let breakers = self.breakers.lock().unwrap();
+        breakers.get(host).map(Breaker::is_open).unwrap_or(false)
+    }
+
+    fn record_breaker_success(&self, host: &str) {
+        let mut breakers = self.breakers.lock().unwrap();
+        breakers
+            .entry(host.to_string())
+            .or_default()
+            .record_success();
+    }
+
+    fn record_breaker_failure(&self, host: &str) {
+        let mut breakers = self.breakers.lock().unwrap();
+        breakers
+            .entry(host.to_string())
+            .or_default()
+            .record_failure();
+    }
+
     #[maybe_async]
     pub async fn query<T: DeserializeOwned>(
         &self,
         adql_query: &str,
     ) -> Result<QueryResult<T>, VizierError> {
-        let request_query = json!({
-            "request": "doQuery",
-            "lang": "ADQL",
-            "format": "json",
-            "query": adql_query
-        });
+        maybe_await!(self.query_as(adql_query, OutputFormat::Json))
+    }
 
-        let response = maybe_await!(self
-            .http_client
-            .get(&self.tap_url)
-            .query(&request_query)
-            .send())
-        .map_err(VizierError::RequestFailed)?;
+    /// Run `adql_query` synchronously, requesting the given [`OutputFormat`].
+    ///
+    /// The JSON path is equivalent to [`Client::query`]; the VOTable path reads
+    /// column datatypes from the `<FIELD>` metadata and maps empty cells to
+    /// null (a `<VALUES null="...">` sentinel is not recognised), while CSV/TSV
+    /// decode the delimited body against its header row.
+    #[maybe_async]
+    pub async fn query_as<T: DeserializeOwned>(
+        &self,
+        adql_query: &str,
+        format: OutputFormat,
+    ) -> Result<QueryResult<T>, VizierError> {
+        let request = self.query_request(adql_query, format.as_tap());
+        let response = maybe_await!(self.send_resilient(request))?;
 
-        if response.status().is_success() {
-            let data =
-                maybe_await!(response.json::<Value>()).map_err(VizierError::RequestFailed)?;
-            let parsed_data = Client::parse_query_result::<T>(data)
-                .map_err(VizierError::DeserializationFailed)?;
+        if !response.is_success() {
+            return Err(response.status_error());
+        }
+
+        match format {
+            OutputFormat::Json => {
+                let data = serde_json::from_slice::<Value>(&response.body)
+                    .map_err(VizierError::DeserializationFailed)?;
+                parse_query_result::<T>(data)
+            }
+            OutputFormat::VoTable => parse_votable_result::<T>(&response.text()),
+            OutputFormat::Csv => parse_delimited_result::<T>(&response.text(), ','),
+            OutputFormat::Tsv => parse_delimited_result::<T>(&response.text(), '\t'),
+        }
+    }
+
+    /// Build the `doQuery` GET request for the sync endpoint.
+    fn query_request(&self, adql_query: &str, format: &str) -> BackendRequest {
+        BackendRequest {
+            method: HttpMethod::Get,
+            url: self.tap_url.clone(),
+            query: vec![
+                ("request".to_string(), "doQuery".to_string()),
+                ("lang".to_string(), "ADQL".to_string()),
+                ("format".to_string(), format.to_string()),
+                ("query".to_string(), adql_query.to_string()),
+            ],
+            form: None,
+        }
+    }
 
-            Ok(parsed_data)
+    /// Run `adql_query` and return a lazily-decoded stream of rows.
+    ///
+    /// Instead of collecting every row into a `Vec<T>` like [`Client::query`],
+    /// this decodes each row into `T` only as it is pulled, so the fully decoded
+    /// table is never held at once. Note that the current backend buffers the
+    /// whole response body and the parsed `Vec<Vec<Value>>` up front, so this
+    /// bounds the *decoded* memory, not the raw body; true body-level streaming
+    /// would require a streaming backend. The column metadata is available up
+    /// front via [`QueryStream::meta`] before any row is pulled. Under `async`
+    /// the returned value is a [`Stream`]; with `is_sync` it is an [`Iterator`].
+    #[maybe_async]
+    pub async fn query_stream<T: DeserializeOwned>(
+        &self,
+        adql_query: &str,
+    ) -> Result<QueryStream<T>, VizierError> {
+        let request = self.query_request(adql_query, OutputFormat::Json.as_tap());
+        let response = maybe_await!(self.send_resilient(request))?;
+
+        if !response.is_success() {
+            return Err(response.status_error());
+        }
+
+        let schema = serde_json::from_slice::<ResponseSchema>(&response.body)
+            .map_err(VizierError::DeserializationFailed)?;
+
+        let column_names = schema.meta.iter().map(|c| c.name.clone()).collect();
+        Ok(QueryStream {
+            meta: schema.meta,
+            column_names,
+            rows: schema.data.into_iter(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Submit `adql_query` as an asynchronous UWS job and wait for its result.
+    ///
+    /// Unlike [`Client::query`], which blocks on a single `/sync` request, this
+    /// creates a job on the `/async` endpoint, polls its phase until it reports
+    /// `COMPLETED`, then fetches the result document. `ERROR`/`ABORTED` phases
+    /// surface as [`VizierError::JobFailed`] and a stalled job eventually fails
+    /// with [`VizierError::JobTimeout`].
+    #[maybe_async]
+    pub async fn query_async<T: DeserializeOwned>(
+        &self,
+        adql_query: &str,
+    ) -> Result<QueryResult<T>, VizierError> {
+        let job = maybe_await!(self.submit_async(adql_query))?;
+        maybe_await!(job.wait())
+    }
+
+    /// Create an asynchronous job for `adql_query` and run it, returning a
+    /// [`QueryJob`] handle that can be polled, awaited, or aborted.
+    #[maybe_async]
+    pub async fn submit_async(&self, adql_query: &str) -> Result<QueryJob<'_, B>, VizierError> {
+        let request = BackendRequest {
+            method: HttpMethod::Post,
+            url: self.async_url(),
+            query: Vec::new(),
+            form: Some(vec![
+                ("REQUEST".to_string(), "doQuery".to_string()),
+                ("LANG".to_string(), "ADQL".to_string()),
+                ("FORMAT".to_string(), "json".to_string()),
+                ("PHASE".to_string(), "RUN".to_string()),
+                ("QUERY".to_string(), adql_query.to_string()),
+            ]),
+        };
+        // Job creation is not idempotent: retrying a POST the server already
+        // accepted would spawn duplicate jobs, so this request bypasses the
+        // retry layer. Only the idempotent job-control requests (the phase and
+        // result GETs, and abort) are retried.
+        let response = maybe_await!(self.send_once(request))?;
+
+        if !response.is_success() {
+            return Err(response.status_error());
+        }
+
+        // Redirects are followed by the backend, so the final URL is the job
+        // resource the server created for us.
+        let job_url = response.url.clone();
+        Ok(QueryJob {
+            client: self,
+            job_url,
+        })
+    }
+}
+
+fn parse_query_result<T: DeserializeOwned>(data: Value) -> Result<QueryResult<T>, VizierError> {
+    let response = serde_json::from_value::<ResponseSchema>(data)
+        .map_err(VizierError::DeserializationFailed)?;
+
+    let mut result = Vec::new();
+    for row in response.data {
+        let mut row_data = Map::new();
+
+        for (i, value) in row.iter().enumerate() {
+            row_data.insert(response.meta[i].name.clone(), value.clone());
+        }
+        let decoded = serde_json::from_value(Value::Object(row_data))
+            .map_err(|err| enrich_schema_error(err, &response.meta))?;
+        result.push(decoded);
+    }
+
+    Ok(QueryResult {
+        meta: response.meta,
+        data: result,
+    })
+}
+
+/// Parse a VOTable document into a [`QueryResult`].
+///
+/// `<FIELD>` elements become [`ColumnMetadata`] (carrying the declared
+/// `datatype`, `ucd`, `unit` and `arraysize`), and each `<TR>`/`<TD>` row is
+/// decoded into `T`, with empty cells mapped to JSON `null`.
+fn parse_votable_result<T: DeserializeOwned>(body: &str) -> Result<QueryResult<T>, VizierError> {
+    let document = roxmltree::Document::parse(body)
+        .map_err(|e| VizierError::UnexpectedSchema(e.to_string()))?;
+
+    let meta: Vec<ColumnMetadata> = document
+        .descendants()
+        .filter(|n| n.has_tag_name("FIELD"))
+        .map(|field| ColumnMetadata {
+            name: field.attribute("name").unwrap_or_default().to_string(),
+            description: field
+                .children()
+                .find(|c| c.has_tag_name("DESCRIPTION"))
+                .and_then(|d| d.text())
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            arraysize: field.attribute("arraysize").map(str::to_string),
+            unit: field.attribute("unit").map(str::to_string),
+            ucd: field.attribute("ucd").unwrap_or_default().to_string(),
+            datatype: field.attribute("datatype").map(str::to_string),
+        })
+        .collect();
+
+    let mut data = Vec::new();
+    for tr in document.descendants().filter(|n| n.has_tag_name("TR")) {
+        let cells: Vec<roxmltree::Node> = tr.children().filter(|n| n.has_tag_name("TD")).collect();
+
+        let mut row = Map::new();
+        for (i, column) in meta.iter().enumerate() {
+            let text = cells.get(i).and_then(|c| c.text()).unwrap_or_default();
+            row.insert(
+                column.name.clone(),
+                cell_to_value(column.datatype.as_deref(), text),
+            );
+        }
+        data.push(
+            serde_json::from_value(Value::Object(row))
+                .map_err(VizierError::DeserializationFailed)?,
+        );
+    }
+
+    Ok(QueryResult { meta, data })
+}
+
+/// Parse a `delimiter`-separated table (CSV/TSV) into a [`QueryResult`].
+///
+/// The first record is taken as the header row supplying column names;
+/// cells are not strongly typed beyond numeric inference, so
+/// [`ColumnMetadata`] produced here carries only the column `name`. Quoting
+/// is honoured, so delimiters and newlines embedded in `"`-quoted fields do
+/// not shift columns.
+fn parse_delimited_result<T: DeserializeOwned>(
+    body: &str,
+    delimiter: char,
+) -> Result<QueryResult<T>, VizierError> {
+    let mut records = parse_delimited_records(body, delimiter).into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| VizierError::UnexpectedSchema("empty delimited response".to_string()))?;
+
+    let meta: Vec<ColumnMetadata> = header
+        .into_iter()
+        .map(|name| ColumnMetadata {
+            name: name.trim().to_string(),
+            description: String::new(),
+            arraysize: None,
+            unit: None,
+            ucd: String::new(),
+            datatype: None,
+        })
+        .collect();
+
+    let mut data = Vec::new();
+    for record in records {
+        let mut row = Map::new();
+        for (i, cell) in record.iter().enumerate() {
+            if let Some(column) = meta.get(i) {
+                row.insert(column.name.clone(), cell_to_value(None, cell.trim()));
+            }
+        }
+        data.push(
+            serde_json::from_value(Value::Object(row))
+                .map_err(VizierError::DeserializationFailed)?,
+        );
+    }
+
+    Ok(QueryResult { meta, data })
+}
+
+/// Split a delimited (CSV/TSV) document into records of fields, honouring
+/// `"`-quoting: delimiters and newlines inside quotes are literal, and a
+/// doubled `""` inside a quoted field is an escaped quote. Empty trailing lines
+/// are skipped.
+fn parse_delimited_records(body: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut seen_field = false;
+
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_quotes = true;
+                seen_field = true;
+            }
+            '\r' => {}
+            c if c == delimiter => {
+                record.push(std::mem::take(&mut field));
+                seen_field = true;
+            }
+            '\n' => {
+                if seen_field || !field.is_empty() || !record.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                seen_field = false;
+            }
+            _ => {
+                field.push(c);
+                seen_field = true;
+            }
+        }
+    }
+
+    if seen_field || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    records
+}
+
+/// Exponential backoff delay for the given (1-based) attempt, capped at
+/// [`RETRY_MAX_DELAY`] and perturbed with a small jitter to avoid thundering
+/// herds against a recovering mirror.
+fn backoff_delay(attempt: u32) -> Duration {
+    let exp = RETRY_BASE_DELAY.saturating_mul(1u32 << (attempt - 1).min(16));
+    let capped = exp.min(RETRY_MAX_DELAY);
+
+    // Cheap jitter in the range [0, capped/2] derived from the wall clock.
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| (d.subsec_nanos() as u64) % (capped.as_millis() as u64 / 2 + 1))
+        .unwrap_or(0);
+
+    capped + Duration::from_millis(jitter)
+}
+
+/// Turn an opaque serde field-mismatch error into a [`VizierError::UnexpectedSchema`]
+/// that names the closest returned column, so users don't have to guess
+/// VizieR's cryptic identifiers. Falls back to [`VizierError::DeserializationFailed`]
+/// when no offending field name can be recovered from the error.
+fn enrich_schema_error(err: serde_json::Error, meta: &[ColumnMetadata]) -> VizierError {
+    let message = err.to_string();
+    let Some(field) = offending_field(&message) else {
+        return VizierError::DeserializationFailed(err);
+    };
+
+    let available: Vec<&str> = meta.iter().map(|c| c.name.as_str()).collect();
+    let suggestion = available
+        .iter()
+        .min_by_key(|name| levenshtein(&field, name))
+        .filter(|name| levenshtein(&field, name) <= field.len().div_ceil(2))
+        .copied();
+
+    let detail = match suggestion {
+        Some(name) => format!("unknown field `{field}`, did you mean `{name}`?"),
+        None => format!("unknown field `{field}`"),
+    };
+
+    VizierError::UnexpectedSchema(format!("{detail} (available: {})", available.join(", ")))
+}
+
+/// Extract the column name from a serde "unknown field" / "missing field"
+/// message, which both quote the identifier between backticks.
+fn offending_field(message: &str) -> Option<String> {
+    if !(message.starts_with("unknown field") || message.starts_with("missing field")) {
+        return None;
+    }
+    let start = message.find('`')? + 1;
+    let end = message[start..].find('`')? + start;
+    Some(message[start..end].to_string())
+}
+
+/// Decode a single result row (a positional value list) into `T`, keying the
+/// values by their column names and enriching any field mismatch.
+fn decode_row<T: DeserializeOwned>(
+    column_names: &[String],
+    meta: &[ColumnMetadata],
+    row: Vec<Value>,
+) -> Result<T, VizierError> {
+    let mut row_data = Map::new();
+    for (name, value) in column_names.iter().zip(row) {
+        row_data.insert(name.clone(), value);
+    }
+    serde_json::from_value(Value::Object(row_data)).map_err(|err| enrich_schema_error(err, meta))
+}
+
+/// Classic dynamic-programming Levenshtein edit distance.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut curr = vec![0; b_chars.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, &cb) in b_chars.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j + 1] + 1).min(curr[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b_chars.len()]
+}
+
+/// Convert a raw table cell into a JSON [`Value`], guided by the VOTable
+/// `datatype` when available. Empty cells become [`Value::Null`].
+fn cell_to_value(datatype: Option<&str>, text: &str) -> Value {
+    if text.is_empty() {
+        return Value::Null;
+    }
+
+    match datatype {
+        Some("short" | "int" | "long" | "unsignedByte") => text
+            .parse::<i64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(text.to_string())),
+        Some("float" | "double") => text
+            .parse::<f64>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(text.to_string())),
+        Some("boolean") => text
+            .parse::<bool>()
+            .map(Value::from)
+            .unwrap_or_else(|_| Value::String(text.to_string())),
+        // No datatype (CSV/TSV) or a textual one: infer a number when possible,
+        // otherwise keep the raw string.
+        _ => {
+            if let Ok(n) = text.parse::<i64>() {
+                Value::from(n)
+            } else if let Ok(n) = text.parse::<f64>() {
+                Value::from(n)
+            } else {
+                Value::String(text.to_string())
+            }
+        }
+    }
+}
+
+/// Handle to an in-flight asynchronous (UWS) query job.
+///
+/// Obtained from [`Client::submit_async`]; keeps a reference to the owning
+/// [`Client`] so it can reuse its HTTP client and poll configuration.
+pub struct QueryJob<'a, B = ReqwestBackend> {
+    client: &'a Client<B>,
+    job_url: String,
+}
+
+impl<B: HttpBackend> QueryJob<'_, B> {
+    /// URL of the job resource on the server.
+    pub fn url(&self) -> &str {
+        &self.job_url
+    }
+
+    /// Fetch the job's current [`Phase`].
+    #[maybe_async]
+    pub async fn phase(&self) -> Result<Phase, VizierError> {
+        let request = BackendRequest {
+            method: HttpMethod::Get,
+            url: format!("{}/phase", self.job_url),
+            query: Vec::new(),
+            form: None,
+        };
+        let response = maybe_await!(self.client.send_resilient(request))?;
+
+        if !response.is_success() {
+            return Err(response.status_error());
+        }
+
+        Ok(Phase::parse(&response.text()))
+    }
+
+    /// Request that the server abort the job.
+    #[maybe_async]
+    pub async fn abort(&self) -> Result<(), VizierError> {
+        let request = BackendRequest {
+            method: HttpMethod::Post,
+            url: format!("{}/phase", self.job_url),
+            query: Vec::new(),
+            form: Some(vec![("PHASE".to_string(), "ABORT".to_string())]),
+        };
+        let response = maybe_await!(self.client.send_resilient(request))?;
+
+        if response.is_success() {
+            Ok(())
         } else {
-            Err(VizierError::NonSuccessStatus(response.status()))
+            Err(response.status_error())
         }
     }
 
-    fn parse_query_result<T: DeserializeOwned>(
-        data: Value,
-    ) -> Result<QueryResult<T>, serde_json::Error> {
-        let response = serde_json::from_value::<ResponseSchema>(data)?;
+    /// Poll the job until it reaches a terminal phase, then fetch and parse its
+    /// result document.
+    #[maybe_async]
+    pub async fn wait<T: DeserializeOwned>(&self) -> Result<QueryResult<T>, VizierError> {
+        let deadline = Instant::now() + self.client.max_wait;
 
-        let mut result = Vec::new();
-        for row in response.data {
-            let mut row_data = Map::new();
+        loop {
+            let phase = maybe_await!(self.phase())?;
+            if phase.is_terminal() {
+                return match phase {
+                    Phase::Completed => maybe_await!(self.result()),
+                    other => Err(VizierError::JobFailed(other)),
+                };
+            }
 
-            for (i, value) in row.iter().enumerate() {
-                row_data.insert(response.meta[i].name.clone(), value.clone());
+            if Instant::now() >= deadline {
+                return Err(VizierError::JobTimeout(self.client.max_wait));
             }
-            result.push(serde_json::from_value(Value::Object(row_data))?);
+            maybe_await!(sleep(self.client.poll_interval));
         }
+    }
 
-        Ok(QueryResult {
-            meta: response.meta,
-            data: result,
-        })
+    /// Fetch the completed job's result document and decode it.
+    #[maybe_async]
+    async fn result<T: DeserializeOwned>(&self) -> Result<QueryResult<T>, VizierError> {
+        let request = BackendRequest {
+            method: HttpMethod::Get,
+            url: format!("{}/results/result", self.job_url),
+            query: Vec::new(),
+            form: None,
+        };
+        let response = maybe_await!(self.client.send_resilient(request))?;
+
+        if !response.is_success() {
+            return Err(response.status_error());
+        }
+
+        let data = serde_json::from_slice::<Value>(&response.body)
+            .map_err(VizierError::DeserializationFailed)?;
+        parse_query_result::<T>(data)
+    }
+}
+
+/// Lazily-decoded sequence of result rows returned by [`Client::query_stream`].
+///
+/// Holds the column metadata and the parsed (but not yet decoded) rows,
+/// decoding each into `T` only as it is pulled so the fully decoded table is
+/// never materialised at once. The raw rows are still buffered up front — this
+/// bounds decoded memory, not the response body. Implements [`Iterator`] under
+/// `is_sync` and [`Stream`] otherwise.
+pub struct QueryStream<T> {
+    meta: Vec<ColumnMetadata>,
+    column_names: Vec<String>,
+    rows: std::vec::IntoIter<Vec<Value>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> QueryStream<T> {
+    /// Column metadata for the result, available before any row is consumed.
+    pub fn meta(&self) -> &[ColumnMetadata] {
+        &self.meta
     }
 }
 
-impl Default for Client {
+#[cfg(feature = "is_sync")]
+impl<T: DeserializeOwned> Iterator for QueryStream<T> {
+    type Item = Result<T, VizierError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        Some(decode_row::<T>(&self.column_names, &self.meta, row))
+    }
+}
+
+#[cfg(not(feature = "is_sync"))]
+impl<T: DeserializeOwned> Stream for QueryStream<T> {
+    type Item = Result<T, VizierError>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // The body is already buffered, so each row is available immediately.
+        let this = self.get_mut();
+        Poll::Ready(
+            this.rows
+                .next()
+                .map(|row| decode_row::<T>(&this.column_names, &this.meta, row)),
+        )
+    }
+}
+
+impl Default for Client<ReqwestBackend> {
     fn default() -> Self {
         Self::new(DEFAULT_VIZIER_TAP_URL)
     }
@@ -153,6 +1197,7 @@ impl Default for Client {
 #[cfg(test)]
 mod tests {
     use serde::Deserialize;
+    use serde_json::json;
 
     use super::*;
 
@@ -201,6 +1246,193 @@ mod tests {
             .unwrap();
     }
 
+    #[cfg(not(feature = "is_sync"))]
+    #[tokio::test]
+    async fn query_async_test() {
+        let client = Client::default();
+
+        let result = client
+            .query_async::<Value>("SELECT TOP 100 * FROM \"I/261/fonac\"")
+            .await
+            .unwrap();
+
+        assert!(result.len() == 100);
+    }
+
+    #[test]
+    fn parse_votable_result_reads_fields_and_rows() {
+        let votable = r#"<VOTABLE><RESOURCE><TABLE>
+            <FIELD name="Bmag" datatype="double" ucd="phot.mag;em.opt.B" unit="mag"/>
+            <FIELD name="recno" datatype="int" ucd="meta.record"/>
+            <DATA><TABLEDATA>
+                <TR><TD>12.34</TD><TD>1</TD></TR>
+                <TR><TD></TD><TD>2</TD></TR>
+            </TABLEDATA></DATA>
+        </TABLE></RESOURCE></VOTABLE>"#;
+
+        #[derive(Deserialize, Debug)]
+        struct Row {
+            #[serde(rename = "Bmag")]
+            bmag: Option<f64>,
+            recno: i32,
+        }
+
+        let result = parse_votable_result::<Row>(votable).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.meta()[0].datatype.as_deref(), Some("double"));
+        assert_eq!(result.data()[0].bmag, Some(12.34));
+        assert_eq!(result.data()[1].bmag, None);
+        assert_eq!(result.data()[1].recno, 2);
+    }
+
+    #[test]
+    fn parse_delimited_result_honours_quoting() {
+        #[derive(Deserialize, Debug)]
+        struct Row {
+            name: String,
+            recno: i32,
+        }
+
+        // The first data row quotes a field containing the delimiter, the second
+        // one containing a newline; neither must shift the following column.
+        let csv = "name,recno\n\"Alpha, the, first\",1\n\"line\nbreak\",2\n";
+
+        let result = parse_delimited_result::<Row>(csv, ',').unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.data()[0].name, "Alpha, the, first");
+        assert_eq!(result.data()[0].recno, 1);
+        assert_eq!(result.data()[1].name, "line\nbreak");
+        assert_eq!(result.data()[1].recno, 2);
+    }
+
+    #[test]
+    fn breaker_trips_open_and_resets_on_success() {
+        let mut breaker = Breaker::default();
+        assert!(!breaker.is_open());
+
+        for _ in 0..BREAKER_FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        assert!(breaker.is_open());
+
+        breaker.record_success();
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn rate_limit_parses_known_headers() {
+        use reqwest::header::{HeaderMap, HeaderValue};
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("100"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+
+        let limits = RateLimit::from_headers(&headers);
+        assert_eq!(limits.limit, Some(100));
+        assert_eq!(limits.remaining, Some(0));
+        assert_eq!(limits.retry_after, Some(Duration::from_secs(30)));
+        assert!(limits.is_exhausted());
+    }
+
+    #[test]
+    fn unknown_field_suggests_closest_column() {
+        #[derive(Deserialize, Debug)]
+        #[allow(non_snake_case, dead_code)]
+        struct Row {
+            Bmg: f64,
+        }
+
+        let data = json!({
+            "metadata": [{ "name": "Bmag", "ucd": "phot.mag" }],
+            "data": [[12.0]]
+        });
+
+        let err = parse_query_result::<Row>(data).unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, VizierError::UnexpectedSchema(_)), "{message}");
+        assert!(message.contains("did you mean `Bmag`?"), "{message}");
+    }
+
+    #[test]
+    fn decode_row_keys_values_by_column() {
+        #[derive(Deserialize, Debug)]
+        struct Row {
+            bmag: f64,
+            recno: i32,
+        }
+
+        let meta = vec![
+            ColumnMetadata {
+                name: "bmag".to_string(),
+                description: String::new(),
+                arraysize: None,
+                unit: None,
+                ucd: String::new(),
+                datatype: None,
+            },
+            ColumnMetadata {
+                name: "recno".to_string(),
+                description: String::new(),
+                arraysize: None,
+                unit: None,
+                ucd: String::new(),
+                datatype: None,
+            },
+        ];
+        let names: Vec<String> = meta.iter().map(|c| c.name.clone()).collect();
+
+        let row = decode_row::<Row>(&names, &meta, vec![json!(12.5), json!(7)]).unwrap();
+        assert_eq!(row.bmag, 12.5);
+        assert_eq!(row.recno, 7);
+    }
+
+    struct MockBackend {
+        body: Vec<u8>,
+    }
+
+    #[maybe_async]
+    impl HttpBackend for MockBackend {
+        async fn execute(&self, _request: BackendRequest) -> Result<BackendResponse, VizierError> {
+            Ok(BackendResponse {
+                status: 200,
+                url: "http://mock/sync".to_string(),
+                headers: reqwest::header::HeaderMap::new(),
+                body: self.body.clone(),
+            })
+        }
+    }
+
+    fn mock_body() -> Vec<u8> {
+        json!({
+            "metadata": [{ "name": "recno", "ucd": "meta.record" }],
+            "data": [[1], [2], [3]]
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[cfg(not(feature = "is_sync"))]
+    #[tokio::test]
+    async fn query_with_mock_backend() {
+        let client = Client::with_backend("http://mock/sync", MockBackend { body: mock_body() });
+
+        let result = client
+            .query::<Value>("SELECT recno FROM mock")
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 3);
+    }
+
+    #[cfg(feature = "is_sync")]
+    #[test]
+    fn query_with_mock_backend_sync() {
+        let client = Client::with_backend("http://mock/sync", MockBackend { body: mock_body() });
+
+        let result = client.query::<Value>("SELECT recno FROM mock").unwrap();
+        assert_eq!(result.len(), 3);
+    }
+
     #[cfg(feature = "is_sync")]
     #[test]
     fn query_test_sync() {